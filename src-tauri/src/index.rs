@@ -0,0 +1,262 @@
+// Persistent file index backing instant search: walk the workspace once,
+// keep the result in SQLite, and let the tree view / search box query it
+// instead of re-walking the disk on every call.
+//
+// `scan_dir` also pairs up removed/added paths that share a fingerprint so a
+// rename shows up as a `rename_events` row instead of a delete + create.
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::FileNode;
+
+/// How many leading bytes of a file are hashed to fingerprint it when
+/// pairing a removed path with a newly added one.
+const FINGERPRINT_SAMPLE_BYTES: usize = 4096;
+
+/// Shared connection to `index.db`, managed on the Tauri builder so every
+/// `scan_dir`/`search_files` call reuses it instead of reopening the file.
+/// Wrapped in an `Arc` so `scan_dir` can hand it to `spawn_blocking` without
+/// holding a borrow across the blocking walk.
+pub struct IndexState(pub Arc<Mutex<Connection>>);
+
+impl IndexState {
+    pub fn new() -> Self {
+        let conn = Connection::open("index.db").expect("failed to open index.db");
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS files (
+                path TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                is_dir INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                modified INTEGER,
+                fingerprint INTEGER
+            )",
+            [],
+        )
+        .expect("failed to create files table");
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS rename_events (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                old_path TEXT NOT NULL,
+                new_path TEXT NOT NULL
+            )",
+            [],
+        )
+        .expect("failed to create rename_events table");
+        IndexState(Arc::new(Mutex::new(conn)))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenameEvent {
+    pub seq: i64,
+    pub old_path: String,
+    pub new_path: String,
+}
+
+/// A removed/added path is only paired as a rename when all three of these
+/// match: size, mtime, and a cheap hash of the first few KB. Any one alone
+/// (especially the hash, which only samples a handful of bytes) collides too
+/// easily between unrelated small or empty files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    size: u64,
+    modified: Option<i64>,
+    hash: u64,
+}
+
+fn fingerprint(path: &Path, size: u64, modified: Option<i64>) -> Option<Fingerprint> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut buf = vec![0u8; FINGERPRINT_SAMPLE_BYTES];
+    let n = file.read(&mut buf).ok()?;
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in &buf[..n] {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    Some(Fingerprint { size, modified, hash })
+}
+
+struct PreviousEntry {
+    path: String,
+    fingerprint: Option<Fingerprint>,
+}
+
+fn previous_snapshot(conn: &Connection, root: &Path) -> rusqlite::Result<Vec<PreviousEntry>> {
+    let root_str = root.to_string_lossy().to_string();
+    // Matching on the bare prefix would also pull in siblings that share it
+    // (e.g. `notes-old` under a `notes` root), so require either an exact
+    // match on `root` itself or a child beyond a path separator.
+    let like_pattern = format!("{}{}%", root_str, std::path::MAIN_SEPARATOR);
+    let mut stmt = conn.prepare("SELECT path, size, modified, fingerprint FROM files WHERE path = ?1 OR path LIKE ?2")?;
+    stmt.query_map(params![root_str, like_pattern], |row| {
+        let size: i64 = row.get(1)?;
+        let modified: Option<i64> = row.get(2)?;
+        let hash: Option<i64> = row.get(3)?;
+        Ok(PreviousEntry {
+            path: row.get(0)?,
+            fingerprint: hash.map(|hash| Fingerprint {
+                size: size as u64,
+                modified,
+                hash: hash as u64,
+            }),
+        })
+    })?
+    .collect()
+}
+
+#[tauri::command(async)]
+pub async fn scan_dir(path: String, state: tauri::State<'_, IndexState>) -> Result<usize, String> {
+    let conn = state.0.clone();
+    tokio::task::spawn_blocking(move || scan_dir_blocking(PathBuf::from(path), conn))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// The actual walk + fingerprinting + SQLite writes. Runs on a blocking
+/// thread since `WalkDir` and per-file fingerprint reads are synchronous I/O
+/// that would otherwise stall a tokio worker for the whole scan.
+fn scan_dir_blocking(root: PathBuf, conn: Arc<Mutex<Connection>>) -> Result<usize, String> {
+    let conn = conn.lock().unwrap();
+
+    let previous = previous_snapshot(&conn, &root).map_err(|e| e.to_string())?;
+    let previous_paths: HashSet<&str> = previous.iter().map(|p| p.path.as_str()).collect();
+
+    let mut seen_paths = HashSet::new();
+    let mut added: Vec<(String, Option<Fingerprint>)> = Vec::new();
+    let mut count = 0;
+
+    for entry in WalkDir::new(&root).into_iter().filter_map(|e| e.ok()) {
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        let path_str = entry.path().to_str().unwrap_or_default().to_string();
+        let size = metadata.len();
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|m| m.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64);
+        let entry_fingerprint = if metadata.is_dir() {
+            None
+        } else {
+            fingerprint(entry.path(), size, modified)
+        };
+
+        conn.execute(
+            "INSERT INTO files (path, name, is_dir, size, modified, fingerprint)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(path) DO UPDATE SET
+                name = excluded.name,
+                is_dir = excluded.is_dir,
+                size = excluded.size,
+                modified = excluded.modified,
+                fingerprint = excluded.fingerprint",
+            params![
+                path_str,
+                entry.file_name().to_str().unwrap_or_default(),
+                metadata.is_dir(),
+                size,
+                modified,
+                entry_fingerprint.map(|fp| fp.hash as i64),
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        if !previous_paths.contains(path_str.as_str()) {
+            added.push((path_str.clone(), entry_fingerprint));
+        }
+        seen_paths.insert(path_str);
+        count += 1;
+    }
+
+    let removed: Vec<&PreviousEntry> = previous
+        .iter()
+        .filter(|p| !seen_paths.contains(p.path.as_str()))
+        .collect();
+
+    for removed_entry in removed {
+        let renamed_to = removed_entry.fingerprint.and_then(|removed_fingerprint| {
+            added
+                .iter()
+                .find(|(_, fp)| *fp == Some(removed_fingerprint))
+                .map(|(path, _)| path.clone())
+        });
+
+        match renamed_to {
+            Some(new_path) => {
+                conn.execute(
+                    "INSERT INTO rename_events (old_path, new_path) VALUES (?1, ?2)",
+                    params![removed_entry.path, new_path],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            None => {
+                // No matching added path: this is a genuine deletion, not a
+                // rename, so the stale row must not linger in `files` or
+                // `search_files` would keep returning it.
+            }
+        }
+        conn.execute("DELETE FROM files WHERE path = ?1", params![removed_entry.path])
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(count)
+}
+
+#[tauri::command(async)]
+pub async fn search_files(query: String, state: tauri::State<'_, IndexState>) -> Result<Vec<FileNode>, String> {
+    let conn = state.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare("SELECT path, name, is_dir FROM files WHERE name LIKE ?1 ORDER BY name")
+        .map_err(|e| e.to_string())?;
+
+    let like_pattern = format!("%{}%", query);
+    let rows = stmt
+        .query_map(params![like_pattern], |row| {
+            Ok(FileNode {
+                path: row.get(0)?,
+                name: row.get(1)?,
+                is_dir: row.get::<_, i64>(2)? != 0,
+                children: Vec::new(),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+/// Returns rename/move events recorded since `since_seq` (exclusive) so the
+/// frontend can reconcile open editors without rescanning the disk.
+#[tauri::command(async)]
+pub async fn get_rename_events(since_seq: i64, state: tauri::State<'_, IndexState>) -> Result<Vec<RenameEvent>, String> {
+    let conn = state.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare("SELECT seq, old_path, new_path FROM rename_events WHERE seq > ?1 ORDER BY seq")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![since_seq], |row| {
+            Ok(RenameEvent {
+                seq: row.get(0)?,
+                old_path: row.get(1)?,
+                new_path: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
@@ -4,100 +4,256 @@
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
 
 use std::fs;
+use std::future::Future;
 use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::OnceLock;
 use tauri::Manager;
 use log::info;
 use arboard::Clipboard;
 use std::sync::Mutex;
 use rusqlite::{params, Connection, Result};
 use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 // use chrono::NaiveDate;
 
+mod index;
+mod vfs;
+mod watcher;
+use index::IndexState;
+use vfs::{Fs, FsState, RealFs};
+use watcher::WatcherState;
+
+static ROOT_FOLDER: OnceLock<PathBuf> = OnceLock::new();
+
+/// Resolves (and caches) the nodian root folder. The underlying path is only
+/// computed, and the directory only created, once per process, so callers on
+/// the async runtime after the first call never touch the disk here.
+fn root_folder_path() -> PathBuf {
+    ROOT_FOLDER
+        .get_or_init(|| {
+            let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+            let root_dir = home_dir.join("nodian");
+            if !root_dir.exists() {
+                fs::create_dir_all(&root_dir).unwrap();
+            }
+            root_dir
+        })
+        .clone()
+}
+
 #[tauri::command]
 fn get_root_folder() -> String {
-    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
-    let root_dir = home_dir.join("nodian");
-    if !root_dir.exists() {
-        fs::create_dir_all(&root_dir).unwrap();
+    root_folder_path().to_str().unwrap().to_string()
+}
+
+/// Refuses to touch the nodian root folder itself, so a bad path from the
+/// frontend can't wipe the whole workspace.
+fn guard_against_root_deletion(path: &std::path::Path) -> Result<(), String> {
+    if normalize_path(path) == normalize_path(&root_folder_path()) {
+        return Err(format!("Refusing to delete the nodian root folder: {}", path.display()));
     }
-    root_dir.to_str().unwrap().to_string()
+    Ok(())
 }
 
-#[tauri::command]
-fn get_file_tree(path: &str) -> Result<FileNode, String> {
+/// Resolves `.`/`..` components lexically, without touching the filesystem,
+/// so a path like `<root>/child/..` compares equal to `<root>` instead of
+/// sailing past the root-deletion guard as a literal string mismatch.
+fn normalize_path(path: &std::path::Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[tauri::command(async)]
+async fn get_file_tree(path: String, state: tauri::State<'_, FsState>) -> Result<FileNode, String> {
     info!("Getting file tree for path: {}", path);
     let path = PathBuf::from(path);
-    if !path.exists() {
+    let fs = state.0.clone();
+    if fs.metadata(&path).await.is_err() {
         return Err(format!("Path does not exist: {}", path.display()));
     }
-    let tree = build_file_tree(&path, true);
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_DIR_READS));
+    let tree = build_file_tree(fs, path, true, semaphore).await;
     info!("File tree: {:?}", tree);
     Ok(tree)
 }
 
-fn build_file_tree(path: &PathBuf, is_root: bool) -> FileNode {
-    let name = path.file_name().unwrap_or_default().to_str().unwrap_or_default().to_string();
-    let is_dir = path.is_dir() || is_root;
-    let children = if is_dir {
-        fs::read_dir(path)
-            .map(|entries| {
-                entries
-                    .filter_map(Result::ok)
-                    .map(|entry| build_file_tree(&entry.path(), false))
-                    .collect()
-            })
-            .unwrap_or_else(|e| {
+/// Caps how many directories are walked at once so a huge tree doesn't spawn
+/// an unbounded number of concurrent `read_dir` calls.
+const MAX_CONCURRENT_DIR_READS: usize = 16;
+
+fn build_file_tree(
+    fs: Arc<dyn Fs>,
+    path: PathBuf,
+    is_root: bool,
+    semaphore: Arc<Semaphore>,
+) -> Pin<Box<dyn Future<Output = FileNode> + Send>> {
+    Box::pin(async move {
+        let name = path.file_name().unwrap_or_default().to_str().unwrap_or_default().to_string();
+        let is_dir = fs.metadata(&path).await.map(|m| m.is_dir).unwrap_or(false) || is_root;
+
+        let children = if is_dir {
+            let permit = semaphore.clone().acquire_owned().await.ok();
+            let entries = fs.read_dir(&path).await.unwrap_or_else(|e| {
                 info!("Error reading directory {}: {}", path.display(), e);
                 Vec::new()
-            })
-    } else {
-        Vec::new()
-    };
+            });
+            drop(permit);
+
+            let mut set = JoinSet::new();
+            for entry in entries {
+                set.spawn(build_file_tree(fs.clone(), entry.path, false, semaphore.clone()));
+            }
+            let mut children = Vec::new();
+            while let Some(result) = set.join_next().await {
+                if let Ok(node) = result {
+                    children.push(node);
+                }
+            }
+            children
+        } else {
+            Vec::new()
+        };
+
+        FileNode {
+            name,
+            path: path.to_str().unwrap_or_default().to_string(),
+            is_dir,
+            children,
+        }
+    })
+}
+
+/// Line ending convention of a note file, detected on read and preserved on
+/// write so editing a CRLF document doesn't silently rewrite it to LF.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum LineEnding {
+    Unix,
+    Windows,
+}
 
-    FileNode {
-        name,
-        path: path.to_str().unwrap_or_default().to_string(),
-        is_dir,
-        children,
+impl LineEnding {
+    fn detect(content: &str) -> Self {
+        if content.contains("\r\n") {
+            LineEnding::Windows
+        } else {
+            LineEnding::Unix
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Unix => "\n",
+            LineEnding::Windows => "\r\n",
+        }
     }
 }
 
-#[tauri::command]
-fn read_file(path: &str) -> Result<String, String> {
-    fs::read_to_string(path).map_err(|e| e.to_string())
+#[derive(Debug, Serialize)]
+struct FileContent {
+    content: String,
+    line_ending: LineEnding,
 }
 
-#[tauri::command]
-fn write_file(path: &str, content: &str) -> Result<(), String> {
-    fs::write(path, content).map_err(|e| e.to_string())
+#[tauri::command(async)]
+async fn read_file(path: String, state: tauri::State<'_, FsState>) -> Result<FileContent, String> {
+    let content = state.0.load(&PathBuf::from(path)).await.map_err(|e| e.to_string())?;
+    let line_ending = LineEnding::detect(&content);
+    Ok(FileContent { content, line_ending })
 }
 
-#[tauri::command]
-fn create_folder(path: &str) -> Result<(), String> {
-    fs::create_dir_all(path).map_err(|e| e.to_string())
+#[tauri::command(async)]
+async fn write_file(
+    path: String,
+    content: String,
+    line_ending: Option<LineEnding>,
+    state: tauri::State<'_, FsState>,
+) -> Result<(), String> {
+    write_file_impl(state.0.as_ref(), PathBuf::from(path), content, line_ending).await
 }
 
-#[tauri::command]
-fn create_file(path: &str) -> Result<(), String> {
-    fs::File::create(path).map_err(|e| e.to_string())?;
-    Ok(())
+async fn write_file_impl(
+    fs: &dyn Fs,
+    path: PathBuf,
+    content: String,
+    line_ending: Option<LineEnding>,
+) -> Result<(), String> {
+    let line_ending = match line_ending {
+        Some(line_ending) => line_ending,
+        // The frontend only omits this for buffers it hasn't touched the
+        // ending of, so fall back to whatever convention is already on disk
+        // rather than the incoming content, which editors typically
+        // normalize to `\n` before sending back. A brand new file has
+        // nothing to preserve, so detect from the content being written.
+        None => match fs.load(&path).await {
+            Ok(existing) => LineEnding::detect(&existing),
+            Err(_) => LineEnding::detect(&content),
+        },
+    };
+    let normalized = content.replace("\r\n", "\n");
+    let output = match line_ending {
+        LineEnding::Unix => normalized,
+        LineEnding::Windows => normalized.replace('\n', "\r\n"),
+    };
+    fs.save(&path, &output).await.map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-fn rename_item(old_path: &str, new_path: &str) -> Result<(), String> {
-    fs::rename(old_path, new_path).map_err(|e| e.to_string())
+#[tauri::command(async)]
+async fn create_folder(path: String, state: tauri::State<'_, FsState>) -> Result<(), String> {
+    state.0.create_dir(&PathBuf::from(path)).await.map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-fn delete_item(path: &str) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    if path.is_dir() {
-        fs::remove_dir_all(path).map_err(|e| e.to_string())
+#[tauri::command(async)]
+async fn create_file(path: String, state: tauri::State<'_, FsState>) -> Result<(), String> {
+    state.0.save(&PathBuf::from(path), "").await.map_err(|e| e.to_string())
+}
+
+#[tauri::command(async)]
+async fn rename_item(old_path: String, new_path: String, state: tauri::State<'_, FsState>) -> Result<(), String> {
+    state
+        .0
+        .rename(&PathBuf::from(old_path), &PathBuf::from(new_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command(async)]
+async fn delete_item(path: String, state: tauri::State<'_, FsState>) -> Result<(), String> {
+    delete_item_impl(state.0.as_ref(), PathBuf::from(path)).await
+}
+
+async fn delete_item_impl(fs: &dyn Fs, path: PathBuf) -> Result<(), String> {
+    guard_against_root_deletion(&path)?;
+    if fs.metadata(&path).await.map(|m| m.is_dir).unwrap_or(false) {
+        fs.remove_dir(&path).await.map_err(|e| e.to_string())
     } else {
-        fs::remove_file(path).map_err(|e| e.to_string())
+        fs.remove_file(&path).await.map_err(|e| e.to_string())
     }
 }
 
+/// Safe-delete variant: sends the file/folder to the OS recycle bin instead
+/// of removing it permanently.
+#[tauri::command(async)]
+async fn trash_item(path: String) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    guard_against_root_deletion(&path)?;
+    trash::delete(&path).map_err(|e| e.to_string())
+}
+
 struct ClipboardState(Mutex<Clipboard>);
 
 #[tauri::command]
@@ -175,14 +331,18 @@ fn delete_event(id: i64) -> Result<(), String> {
 }
 
 #[derive(serde::Serialize, Debug)]
-struct FileNode {
-    name: String,
-    path: String,
-    is_dir: bool,
-    children: Vec<FileNode>,
+pub(crate) struct FileNode {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) is_dir: bool,
+    pub(crate) children: Vec<FileNode>,
 }
 
 fn main() {
+    // Resolve and create the root folder up front, before any async command
+    // can reach `guard_against_root_deletion` and block a tokio worker on it.
+    root_folder_path();
+
     tauri::Builder::default()
         .setup(|app| {
             #[cfg(debug_assertions)]
@@ -193,6 +353,9 @@ fn main() {
             Ok(())
         })
         .manage(ClipboardState(Mutex::new(Clipboard::new().unwrap())))
+        .manage(WatcherState::new())
+        .manage(IndexState::new())
+        .manage(FsState(Arc::new(RealFs)))
         .invoke_handler(tauri::generate_handler![
             get_root_folder,
             get_file_tree,
@@ -202,12 +365,88 @@ fn main() {
             create_file,
             rename_item,
             delete_item,
+            trash_item,
             get_clipboard_content,
             set_clipboard_content,
             get_events,
             add_event,
-            delete_event
+            delete_event,
+            watcher::start_watch,
+            watcher::stop_watch,
+            index::scan_dir,
+            index::search_files,
+            index::get_rename_events
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use vfs::FakeFs;
+
+    #[tokio::test]
+    async fn write_file_preserves_existing_crlf_ending_by_default() {
+        let fs = FakeFs::new();
+        let path = PathBuf::from("/root/note.md");
+        fs.save(&path, "line one\r\nline two").await.unwrap();
+
+        write_file_impl(&fs, path.clone(), "line one\nline three".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(fs.load(&path).await.unwrap(), "line one\r\nline three");
+    }
+
+    #[tokio::test]
+    async fn write_file_defaults_new_file_to_content_ending() {
+        let fs = FakeFs::new();
+        let path = PathBuf::from("/root/new.md");
+
+        write_file_impl(&fs, path.clone(), "line one\r\nline two".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(fs.load(&path).await.unwrap(), "line one\r\nline two");
+    }
+
+    #[tokio::test]
+    async fn build_file_tree_recurses_into_subdirectories() {
+        let fs = Arc::new(FakeFs::new());
+        fs.create_dir(Path::new("/root")).await.unwrap();
+        fs.create_dir(Path::new("/root/sub")).await.unwrap();
+        fs.save(Path::new("/root/sub/note.md"), "hello").await.unwrap();
+
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_DIR_READS));
+        let tree = build_file_tree(fs, PathBuf::from("/root"), true, semaphore).await;
+
+        assert_eq!(tree.children.len(), 1);
+        let sub = &tree.children[0];
+        assert_eq!(sub.name, "sub");
+        assert_eq!(sub.children.len(), 1);
+        assert_eq!(sub.children[0].name, "note.md");
+    }
+
+    #[tokio::test]
+    async fn delete_item_refuses_to_remove_root_folder() {
+        let fs = FakeFs::new();
+        let root = root_folder_path();
+        fs.create_dir(&root).await.unwrap();
+
+        let result = delete_item_impl(&fs, root).await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn guard_against_root_deletion_catches_dot_dot_escape() {
+        let root = root_folder_path();
+        let escaped = root.join("child").join("..");
+
+        let result = guard_against_root_deletion(&escaped);
+
+        assert!(result.is_err());
+    }
+}
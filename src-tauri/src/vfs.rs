@@ -0,0 +1,203 @@
+// Filesystem abstraction so the command layer doesn't call `tokio::fs`
+// directly: commands depend on `Fs`, production wires up `RealFs`, and tests
+// can swap in `FakeFs` instead of touching the real disk.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+#[derive(Debug, Clone)]
+pub struct DirEntryInfo {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Metadata {
+    pub is_dir: bool,
+    pub len: u64,
+}
+
+#[async_trait]
+pub trait Fs: Send + Sync {
+    async fn create_dir(&self, path: &Path) -> std::io::Result<()>;
+    async fn load(&self, path: &Path) -> std::io::Result<String>;
+    async fn save(&self, path: &Path, content: &str) -> std::io::Result<()>;
+    async fn rename(&self, old_path: &Path, new_path: &Path) -> std::io::Result<()>;
+    async fn remove_file(&self, path: &Path) -> std::io::Result<()>;
+    async fn remove_dir(&self, path: &Path) -> std::io::Result<()>;
+    async fn read_dir(&self, path: &Path) -> std::io::Result<Vec<DirEntryInfo>>;
+    async fn metadata(&self, path: &Path) -> std::io::Result<Metadata>;
+}
+
+/// Shared handle to the active `Fs` backend, managed on the Tauri builder.
+pub struct FsState(pub Arc<dyn Fs>);
+
+pub struct RealFs;
+
+#[async_trait]
+impl Fs for RealFs {
+    async fn create_dir(&self, path: &Path) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(path).await
+    }
+
+    async fn load(&self, path: &Path) -> std::io::Result<String> {
+        tokio::fs::read_to_string(path).await
+    }
+
+    async fn save(&self, path: &Path, content: &str) -> std::io::Result<()> {
+        tokio::fs::write(path, content).await
+    }
+
+    async fn rename(&self, old_path: &Path, new_path: &Path) -> std::io::Result<()> {
+        tokio::fs::rename(old_path, new_path).await
+    }
+
+    async fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+        tokio::fs::remove_file(path).await
+    }
+
+    async fn remove_dir(&self, path: &Path) -> std::io::Result<()> {
+        tokio::fs::remove_dir_all(path).await
+    }
+
+    async fn read_dir(&self, path: &Path) -> std::io::Result<Vec<DirEntryInfo>> {
+        let mut read_dir = tokio::fs::read_dir(path).await?;
+        let mut entries = Vec::new();
+        while let Some(entry) = read_dir.next_entry().await? {
+            let is_dir = entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false);
+            entries.push(DirEntryInfo { path: entry.path(), is_dir });
+        }
+        Ok(entries)
+    }
+
+    async fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        let metadata = tokio::fs::metadata(path).await?;
+        Ok(Metadata { is_dir: metadata.is_dir(), len: metadata.len() })
+    }
+}
+
+#[cfg(test)]
+pub use fake::FakeFs;
+
+#[cfg(test)]
+mod fake {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct FakeFsState {
+        files: HashMap<PathBuf, String>,
+        dirs: HashSet<PathBuf>,
+    }
+
+    /// In-memory `Fs` for tests.
+    #[derive(Default)]
+    pub struct FakeFs {
+        state: Mutex<FakeFsState>,
+    }
+
+    impl FakeFs {
+        pub fn new() -> Self {
+            FakeFs::default()
+        }
+    }
+
+    #[async_trait]
+    impl Fs for FakeFs {
+        async fn create_dir(&self, path: &Path) -> std::io::Result<()> {
+            self.state.lock().unwrap().dirs.insert(path.to_path_buf());
+            Ok(())
+        }
+
+        async fn load(&self, path: &Path) -> std::io::Result<String> {
+            self.state
+                .lock()
+                .unwrap()
+                .files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "file not found"))
+        }
+
+        async fn save(&self, path: &Path, content: &str) -> std::io::Result<()> {
+            self.state.lock().unwrap().files.insert(path.to_path_buf(), content.to_string());
+            Ok(())
+        }
+
+        async fn rename(&self, old_path: &Path, new_path: &Path) -> std::io::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            let content = state
+                .files
+                .remove(old_path)
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "file not found"))?;
+            state.files.insert(new_path.to_path_buf(), content);
+            Ok(())
+        }
+
+        async fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            self.state
+                .lock()
+                .unwrap()
+                .files
+                .remove(path)
+                .map(|_| ())
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "file not found"))
+        }
+
+        async fn remove_dir(&self, path: &Path) -> std::io::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            state.dirs.remove(path);
+            state.files.retain(|p, _| !p.starts_with(path));
+            Ok(())
+        }
+
+        async fn read_dir(&self, path: &Path) -> std::io::Result<Vec<DirEntryInfo>> {
+            let state = self.state.lock().unwrap();
+            let mut entries: Vec<DirEntryInfo> = state
+                .dirs
+                .iter()
+                .filter(|dir| dir.parent() == Some(path))
+                .map(|dir| DirEntryInfo { path: dir.clone(), is_dir: true })
+                .collect();
+            entries.extend(
+                state
+                    .files
+                    .keys()
+                    .filter(|file| file.parent() == Some(path))
+                    .map(|file| DirEntryInfo { path: file.clone(), is_dir: false }),
+            );
+            Ok(entries)
+        }
+
+        async fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            let state = self.state.lock().unwrap();
+            if let Some(content) = state.files.get(path) {
+                Ok(Metadata { is_dir: false, len: content.len() as u64 })
+            } else if state.dirs.contains(path) {
+                Ok(Metadata { is_dir: true, len: 0 })
+            } else {
+                Err(std::io::Error::new(std::io::ErrorKind::NotFound, "not found"))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn save_and_load_roundtrip() {
+        let fs = FakeFs::new();
+        fs.save(Path::new("/root/note.md"), "hello").await.unwrap();
+        assert_eq!(fs.load(Path::new("/root/note.md")).await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn rename_moves_content_to_new_path() {
+        let fs = FakeFs::new();
+        fs.save(Path::new("/root/old.md"), "hello").await.unwrap();
+        fs.rename(Path::new("/root/old.md"), Path::new("/root/new.md")).await.unwrap();
+        assert!(fs.load(Path::new("/root/old.md")).await.is_err());
+        assert_eq!(fs.load(Path::new("/root/new.md")).await.unwrap(), "hello");
+    }
+
+}
@@ -0,0 +1,99 @@
+// Live file-tree watching: keeps one `notify` watcher per watched root and
+// forwards coalesced fs-change events to the frontend instead of making the
+// UI poll by re-walking the tree.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::info;
+use notify::{Event as NotifyEvent, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+/// Bursts of events arriving within this window are coalesced into a single
+/// batch of `fs-change` emits so a single save doesn't flood the channel.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+pub struct WatcherState(pub Mutex<HashMap<PathBuf, RecommendedWatcher>>);
+
+impl WatcherState {
+    pub fn new() -> Self {
+        WatcherState(Mutex::new(HashMap::new()))
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct FsChangeEvent {
+    kind: &'static str,
+    path: String,
+    new_path: Option<String>,
+}
+
+#[tauri::command]
+pub fn start_watch(path: String, app: AppHandle, state: tauri::State<WatcherState>) -> Result<(), String> {
+    let mut watchers = state.0.lock().unwrap();
+    let watch_path = PathBuf::from(&path);
+    if watchers.contains_key(&watch_path) {
+        return Ok(());
+    }
+
+    let (tx, rx) = mpsc::channel::<notify::Result<NotifyEvent>>();
+    let mut watcher: RecommendedWatcher = notify::recommended_watcher(tx).map_err(|e| e.to_string())?;
+    watcher
+        .watch(&watch_path, RecursiveMode::Recursive)
+        .map_err(|e| e.to_string())?;
+
+    let app_handle = app.clone();
+    std::thread::spawn(move || watch_loop(app_handle, rx));
+
+    watchers.insert(watch_path, watcher);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_watch(path: String, state: tauri::State<WatcherState>) -> Result<(), String> {
+    state.0.lock().unwrap().remove(&PathBuf::from(path));
+    Ok(())
+}
+
+fn watch_loop(app: AppHandle, rx: mpsc::Receiver<notify::Result<NotifyEvent>>) {
+    let mut pending: Vec<NotifyEvent> = Vec::new();
+    loop {
+        match rx.recv_timeout(DEBOUNCE) {
+            Ok(Ok(event)) => pending.push(event),
+            Ok(Err(e)) => info!("Watch error: {}", e),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if !pending.is_empty() {
+                    emit_events(&app, std::mem::take(&mut pending));
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+fn emit_events(app: &AppHandle, events: Vec<NotifyEvent>) {
+    for event in events {
+        let path = match event.paths.first() {
+            Some(path) => path.display().to_string(),
+            None => continue,
+        };
+
+        let (kind, new_path): (&'static str, Option<String>) = match event.kind {
+            EventKind::Create(_) => ("create", None),
+            EventKind::Modify(notify::event::ModifyKind::Name(_)) if event.paths.len() > 1 => {
+                ("rename", event.paths.get(1).map(|p| p.display().to_string()))
+            }
+            EventKind::Modify(_) => ("modify", None),
+            EventKind::Remove(_) => ("remove", None),
+            _ => continue,
+        };
+
+        if let Err(e) = app.emit_all("fs-change", FsChangeEvent { kind, path, new_path }) {
+            info!("Failed to emit fs-change: {}", e);
+        }
+    }
+}